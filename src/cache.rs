@@ -0,0 +1,90 @@
+use crate::{OsmNode, OsmRailway, RailwaySegment};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+/// Magic bytes at the head of every cache file so a stale or foreign file is
+/// rejected rather than misinterpreted.
+const MAGIC: [u8; 4] = *b"O2RC";
+
+/// Bump this whenever the on-disk layout of [`CachedNetwork`] changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// The fully parsed and segmented network, ready to be serialized to the cache.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedNetwork {
+    pub railways: Vec<OsmRailway>,
+    pub nodes: HashMap<i64, OsmNode>,
+    pub segments: Vec<RailwaySegment>,
+}
+
+/// Stream the source `.pbf` through SHA3-256 so we can tell when the input has
+/// changed without holding the whole file in memory.
+pub fn hash_pbf(path: &str) -> io::Result<[u8; 32]> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha3_256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// Serialize the network to `cache_path` behind a versioned header carrying the
+/// digest of `source_pbf`, using compact `bincode` rather than pretty JSON.
+pub fn write_cache(cache_path: &str, source_pbf: &str, network: &CachedNetwork) -> io::Result<()> {
+    let digest = hash_pbf(source_pbf)?;
+    let payload = bincode::serialize(network)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut writer = BufWriter::new(File::create(cache_path)?);
+    writer.write_all(&MAGIC)?;
+    writer.write_u32::<LittleEndian>(FORMAT_VERSION)?;
+    writer.write_all(&digest)?;
+    writer.write_all(&payload)?;
+    writer.flush()
+}
+
+/// Load the cached network if `cache_path` exists, was written by this format
+/// version, and its embedded digest still matches `source_pbf`. Returns `None`
+/// when the cache is missing, foreign, outdated, or stale so the caller can
+/// rebuild it.
+pub fn load_cache(cache_path: &str, source_pbf: &str) -> io::Result<Option<CachedNetwork>> {
+    let mut reader = match File::open(cache_path) {
+        Ok(file) => BufReader::new(file),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Ok(None);
+    }
+
+    if reader.read_u32::<LittleEndian>()? != FORMAT_VERSION {
+        return Ok(None);
+    }
+
+    let mut stored_digest = [0u8; 32];
+    reader.read_exact(&mut stored_digest)?;
+    if stored_digest != hash_pbf(source_pbf)? {
+        return Ok(None);
+    }
+
+    let mut payload = Vec::new();
+    reader.read_to_end(&mut payload)?;
+    let network = bincode::deserialize(&payload)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(Some(network))
+}
@@ -0,0 +1,139 @@
+use crate::{OsmNode, RailwaySegment};
+use std::collections::HashMap;
+
+/// Summary of a single connected component of the railway network.
+#[derive(Debug, Clone)]
+pub struct ComponentSummary {
+    pub component: usize,
+    pub segment_count: usize,
+    pub total_km: f64,
+}
+
+/// A disjoint-set over dense node indices with path compression and union by
+/// size. Used to group nodes that are connected through shared endpoints.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return;
+        }
+        // Attach the smaller tree under the larger one.
+        let (small, large) = if self.size[ra] < self.size[rb] {
+            (ra, rb)
+        } else {
+            (rb, ra)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+    }
+}
+
+/// Label every segment with the connected component it belongs to and summarize
+/// each component by its segment count and total length in kilometers.
+///
+/// The adjacency is the same node-id graph used for routing: consecutive nodes
+/// within a segment are unioned, so stretches of track that share a node fall
+/// into the same component. Returns a map from `way_id` to component id plus the
+/// component summaries sorted largest-first by total distance. Every segment of
+/// a given way is connected, so the mapping is well defined per `way_id`.
+pub fn connected_components(
+    segments: &[RailwaySegment],
+    node_locations: &HashMap<i64, OsmNode>,
+) -> (HashMap<i64, usize>, Vec<ComponentSummary>) {
+    // Assign each distinct node id a dense index for the union-find.
+    let mut index_of: HashMap<i64, usize> = HashMap::new();
+    for segment in segments {
+        for &node_id in &segment.node_ids {
+            let next = index_of.len();
+            index_of.entry(node_id).or_insert(next);
+        }
+    }
+
+    let mut uf = UnionFind::new(index_of.len());
+    for segment in segments {
+        for pair in segment.node_ids.windows(2) {
+            uf.union(index_of[&pair[0]], index_of[&pair[1]]);
+        }
+    }
+
+    // Turn canonical roots into compact, sequential component ids.
+    let mut component_of_root: HashMap<usize, usize> = HashMap::new();
+    let mut node_components: HashMap<i64, usize> = HashMap::new();
+    for (&node_id, &index) in &index_of {
+        let root = uf.find(index);
+        let next = component_of_root.len();
+        let component = *component_of_root.entry(root).or_insert(next);
+        node_components.insert(node_id, component);
+    }
+
+    // Accumulate segment counts and distances per component, and map each
+    // way to the component its segments belong to.
+    let mut way_components: HashMap<i64, usize> = HashMap::new();
+    let mut by_component: HashMap<usize, (usize, f64)> = HashMap::new();
+    for segment in segments {
+        let Some(&first) = segment.node_ids.first() else {
+            continue;
+        };
+        let component = node_components[&first];
+        way_components.insert(segment.way_id, component);
+        let entry = by_component.entry(component).or_insert((0, 0.0));
+        entry.0 += 1;
+        // `get_distance` returns meters; summaries are reported in kilometers.
+        entry.1 += segment.get_distance(node_locations) / 1000.0;
+    }
+
+    let mut summaries: Vec<ComponentSummary> = by_component
+        .into_iter()
+        .map(|(component, (segment_count, total_km))| ComponentSummary {
+            component,
+            segment_count,
+            total_km,
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| {
+        b.total_km
+            .partial_cmp(&a.total_km)
+            .expect("distances are never NaN")
+            .then(b.segment_count.cmp(&a.segment_count))
+    });
+
+    (way_components, summaries)
+}
+
+#[test]
+fn test_connected_components() {
+    // Two disjoint chains: way 1 (1-2-3) and way 2 (10-11).
+    let segments = vec![
+        RailwaySegment { name: "a".to_string(), way_id: 1, node_ids: vec![1, 2] },
+        RailwaySegment { name: "b".to_string(), way_id: 1, node_ids: vec![2, 3] },
+        RailwaySegment { name: "c".to_string(), way_id: 2, node_ids: vec![10, 11] },
+    ];
+
+    let (way_components, summaries) = connected_components(&segments, &HashMap::new());
+
+    assert_ne!(way_components[&1], way_components[&2]);
+    assert_eq!(summaries.len(), 2);
+    // The larger component (two segments) sorts first.
+    assert_eq!(summaries[0].segment_count, 2);
+}
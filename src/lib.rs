@@ -4,13 +4,17 @@ use osmpbf::{Element, ElementReader, WayNodeLocation};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+pub mod cache;
+pub mod components;
 mod reader;
+pub mod routing;
+pub mod spatial;
 
 pub trait HasTags {
     fn tags(&self) -> &HashMap<String, String>;
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OsmRailway {
     // Represent the railway from OSM - moderately untransformed, without any splitting done.
     pub name: String,
@@ -166,13 +170,93 @@ impl HasTags for OsmNode {
     }
 }
 
-/// Read an OSM file and parse out all of the railways.
-pub fn collect_all_railways(file: &str) -> Vec<OsmRailway> {
+/// Configuration describing which ways count as railways. Beyond the set of
+/// accepted `railway=*` values it can require or exclude arbitrary tags, so a
+/// caller can, for example, keep only `usage=main` lines or drop `service=yard`
+/// sidings. The default accepts `railway=rail` only, preserving the historic
+/// behavior.
+#[derive(Debug, Clone)]
+pub struct RailwayFilter {
+    accepted_railway_values: HashSet<String>,
+    required_tags: Vec<(String, String)>,
+    excluded_tags: Vec<(String, String)>,
+}
+
+impl Default for RailwayFilter {
+    fn default() -> Self {
+        RailwayFilter {
+            accepted_railway_values: HashSet::from(["rail".to_string()]),
+            required_tags: Vec::new(),
+            excluded_tags: Vec::new(),
+        }
+    }
+}
+
+impl RailwayFilter {
+    /// Build a filter accepting the given `railway=*` values.
+    pub fn new<I, S>(accepted_railway_values: I) -> RailwayFilter
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        RailwayFilter {
+            accepted_railway_values: accepted_railway_values
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            required_tags: Vec::new(),
+            excluded_tags: Vec::new(),
+        }
+    }
+
+    /// Also accept ways tagged with this `railway=*` value.
+    pub fn accept(mut self, value: impl Into<String>) -> RailwayFilter {
+        self.accepted_railway_values.insert(value.into());
+        self
+    }
+
+    /// Require that the way carries this exact tag.
+    pub fn require(mut self, key: impl Into<String>, value: impl Into<String>) -> RailwayFilter {
+        self.required_tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Drop any way carrying this exact tag.
+    pub fn exclude(mut self, key: impl Into<String>, value: impl Into<String>) -> RailwayFilter {
+        self.excluded_tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Whether `way` satisfies the filter: it carries an accepted `railway=*`
+    /// value, every required tag, and none of the excluded tags.
+    pub fn accepts_way(&self, way: &osmpbf::Way) -> bool {
+        let railway_ok = way
+            .tags()
+            .any(|(k, v)| k == "railway" && self.accepted_railway_values.contains(v));
+        if !railway_ok {
+            return false;
+        }
+
+        let has_required = self
+            .required_tags
+            .iter()
+            .all(|(rk, rv)| way.tags().any(|(k, v)| k == rk && v == rv));
+        if !has_required {
+            return false;
+        }
+
+        !self
+            .excluded_tags
+            .iter()
+            .any(|(ek, ev)| way.tags().any(|(k, v)| k == ek && v == ev))
+    }
+}
+
+/// Read an OSM file and parse out all of the railways matching `filter`.
+pub fn collect_all_railways(file: &str, filter: &RailwayFilter) -> Vec<OsmRailway> {
     let railways =
         ElementReader::<std::fs::File>::collect_filtered(file, |element| match element {
-            Element::Way(way) if way.tags().any(|(k, v)| k == "railway" && v == "rail") => {
-                Some(OsmRailway::from_osm_way(&way))
-            }
+            Element::Way(way) if filter.accepts_way(&way) => Some(OsmRailway::from_osm_way(&way)),
             _ => None,
         })
         .expect("Error collecting filtered elements");
@@ -224,6 +308,34 @@ pub fn collect_nodes(file: &str) -> HashMap<i64, OsmNode> {
     node_coords
 }
 
+/// Return a mapping of all of the keys: {value: count} that are found in all of the tags of the
+/// provided elements, optionally dropping any value seen fewer than `threshold` times.
+pub fn get_used_tags<T: HasTags>(
+    elements: &Vec<T>,
+    threshold: Option<i64>,
+) -> HashMap<String, HashMap<String, i64>> {
+    let mut used_tags: HashMap<String, HashMap<String, i64>> = HashMap::new();
+
+    for elem in elements.iter() {
+        for (k, v) in elem.tags() {
+            let existing_vals = used_tags.entry(k.clone()).or_insert(HashMap::new());
+            let count = existing_vals.entry(v.clone()).or_insert(0);
+            *count += 1;
+        }
+    }
+
+    match threshold {
+        None => used_tags,
+        Some(t) => {
+            used_tags.retain(|_, value_map| {
+                value_map.retain(|_, count| *count >= t);
+                !value_map.is_empty() // Remove key if all its values were filtered out
+            });
+            used_tags
+        }
+    }
+}
+
 pub fn segment_railways(railways: Vec<OsmRailway>) -> Vec<RailwaySegment> {
     let node_counts = OsmRailway::get_used_node_counts(&railways);
 
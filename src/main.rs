@@ -1,10 +1,186 @@
-use osm2rail::{collect_all_railways, collect_nodes, segment_railways};
+use std::fs::File;
 
-mod reader;
+use clap::{Args, Parser, Subcommand};
+
+use osm2rail::cache::{load_cache, write_cache, CachedNetwork};
+use osm2rail::routing::RailwayGraph;
+use osm2rail::spatial::RailwayIndex;
+use osm2rail::{
+    collect_all_railways, collect_nodes, get_used_tags, segment_railways, RailwayFilter,
+};
+
+/// Tools for turning an OSM `.pbf` extract into a routable railway network.
+#[derive(Parser)]
+#[command(name = "osm2rail", about = "Parse, cache and route over OSM railways")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Parse a `.pbf`, segment the network and write the binary cache.
+    Preprocess(PreprocessArgs),
+    /// Report the tag values used across railways and nodes.
+    Tags(TagsArgs),
+    /// Find the shortest path between two points over the cached network.
+    Route(RouteArgs),
+}
+
+#[derive(Args)]
+struct PreprocessArgs {
+    /// Source OSM `.pbf` extract.
+    #[arg(long)]
+    input: String,
+    /// Destination path for the binary cache.
+    #[arg(long)]
+    output: String,
+}
+
+#[derive(Args)]
+struct TagsArgs {
+    /// Source OSM `.pbf` extract.
+    #[arg(long)]
+    input: String,
+    /// Only report tag values seen at least this many times.
+    #[arg(long)]
+    threshold: Option<i64>,
+}
+
+#[derive(Args)]
+struct RouteArgs {
+    /// Source OSM `.pbf` extract, used to validate and rebuild the cache.
+    #[arg(long)]
+    input: String,
+    /// Binary cache produced by `preprocess`.
+    #[arg(long)]
+    cache: String,
+    /// Start node id.
+    #[arg(long, conflicts_with = "from_coord")]
+    from: Option<i64>,
+    /// End node id.
+    #[arg(long, conflicts_with = "to_coord")]
+    to: Option<i64>,
+    /// Start coordinate as `lat,lon`, snapped to the nearest node.
+    #[arg(long)]
+    from_coord: Option<String>,
+    /// End coordinate as `lat,lon`, snapped to the nearest node.
+    #[arg(long)]
+    to_coord: Option<String>,
+}
 
 fn main() {
-    let file = "./osm_data/railways-in-us-northeast.osm.pbf";
-    let node_metadata = collect_nodes(file);
-    let railways = collect_all_railways(file);
-    segment_railways(railways);
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Preprocess(args) => preprocess(&args),
+        Command::Tags(args) => tags(&args),
+        Command::Route(args) => route(&args),
+    }
+}
+
+/// Run the full parse + segmentation pipeline for `input`.
+fn build_network(input: &str) -> CachedNetwork {
+    let nodes = collect_nodes(input);
+    let railways = collect_all_railways(input, &RailwayFilter::default());
+    // `segment_railways` consumes its input, so segment a clone and keep the
+    // original railways for the cache.
+    let segments = segment_railways(railways.clone());
+
+    CachedNetwork {
+        railways,
+        nodes,
+        segments,
+    }
+}
+
+fn preprocess(args: &PreprocessArgs) {
+    let network = build_network(&args.input);
+    write_cache(&args.output, &args.input, &network).expect("Failed to write cache");
+    println!(
+        "Wrote {} segments to {}",
+        network.segments.len(),
+        args.output
+    );
+}
+
+fn tags(args: &TagsArgs) {
+    let railways = collect_all_railways(&args.input, &RailwayFilter::default());
+    let nodes = collect_nodes(&args.input);
+
+    let used_railway_tags = get_used_tags(&railways, args.threshold);
+    let used_node_tags = get_used_tags(&nodes.into_values().collect(), args.threshold);
+
+    // Emit both tag maps as a single top-level JSON object. The railway and node
+    // count lines printed by `collect_all_railways` and `collect_nodes` precede
+    // it on stdout.
+    let combined = serde_json::json!({
+        "railways": used_railway_tags,
+        "nodes": used_node_tags,
+    });
+    serde_json::to_writer_pretty(std::io::stdout(), &combined).unwrap();
+    println!();
+}
+
+fn route(args: &RouteArgs) {
+    let network = load_or_build(&args.cache, &args.input);
+
+    let index = RailwayIndex::build(&network.segments, &network.nodes);
+    let from = resolve_endpoint("from", args.from, args.from_coord.as_deref(), &index);
+    let to = resolve_endpoint("to", args.to, args.to_coord.as_deref(), &index);
+
+    let graph = RailwayGraph::from_segments(&network.segments, &network.nodes);
+
+    match graph.shortest_path_astar(from, to, &network.nodes) {
+        Some((path, distance)) => {
+            println!("Route of {:.3} km over {} nodes:", distance, path.len());
+            for node_id in path {
+                println!("{node_id}");
+            }
+        }
+        None => println!("No path found between {from} and {to}"),
+    }
+}
+
+/// Load the cache, rebuilding and rewriting it if it is missing or stale.
+fn load_or_build(cache: &str, input: &str) -> CachedNetwork {
+    match load_cache(cache, input).expect("Failed to read cache") {
+        Some(network) => network,
+        None => {
+            let network = build_network(input);
+            write_cache(cache, input, &network).expect("Failed to write cache");
+            network
+        }
+    }
+}
+
+/// Resolve a node id from either an explicit id or a `lat,lon` coordinate that
+/// is snapped to the nearest node.
+fn resolve_endpoint(
+    label: &str,
+    node_id: Option<i64>,
+    coord: Option<&str>,
+    index: &RailwayIndex,
+) -> i64 {
+    if let Some(id) = node_id {
+        return id;
+    }
+
+    let coord = coord.unwrap_or_else(|| panic!("Missing --{label} or --{label}-coord"));
+    let (lat, lon) = parse_coord(coord);
+    let (snapped, dist_km) = index
+        .snap(lat, lon)
+        .unwrap_or_else(|| panic!("No railway node near the {label} coordinate"));
+    println!("Snapped {label} {coord} to node {snapped} ({dist_km:.3} km away)");
+    snapped
+}
+
+fn parse_coord(coord: &str) -> (f64, f64) {
+    let (lat, lon) = coord
+        .split_once(',')
+        .unwrap_or_else(|| panic!("Expected a `lat,lon` coordinate, got `{coord}`"));
+    (
+        lat.trim().parse().expect("Invalid latitude"),
+        lon.trim().parse().expect("Invalid longitude"),
+    )
 }
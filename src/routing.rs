@@ -0,0 +1,207 @@
+use crate::{OsmNode, RailwaySegment};
+use geo::{Distance, Haversine, Point};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A wrapper around `f64` that is totally ordered so distances can live in a
+/// `BinaryHeap`. Panics if built from `NaN`, which should never happen for a
+/// real great-circle distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NonNan(f64);
+
+impl NonNan {
+    pub fn new(value: f64) -> NonNan {
+        assert!(!value.is_nan(), "NonNan constructed from NaN");
+        NonNan(value)
+    }
+
+    pub fn into_inner(self) -> f64 {
+        self.0
+    }
+}
+
+impl Eq for NonNan {}
+
+impl PartialOrd for NonNan {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NonNan {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .expect("NonNan should never hold NaN")
+    }
+}
+
+/// An entry in the search frontier. Ordered by `priority` so that a `BinaryHeap`
+/// (which is a max-heap) pops the *lowest* tentative distance first.
+#[derive(Debug, PartialEq, Eq)]
+struct Candidate {
+    priority: NonNan,
+    node_id: i64,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse so the smallest priority is considered greatest by the heap.
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// An adjacency graph over OSM node ids. Each `RailwaySegment` contributes an
+/// undirected edge between its first and last node, weighted by its length as
+/// reported by [`RailwaySegment::get_distance`].
+pub struct RailwayGraph {
+    adjacency: HashMap<i64, Vec<(i64, f64)>>,
+}
+
+impl RailwayGraph {
+    /// Build the routing graph from the segmented railways. Segments are already
+    /// split at intersection nodes, so their endpoints are the graph vertices.
+    pub fn from_segments(
+        segments: &[RailwaySegment],
+        node_locations: &HashMap<i64, OsmNode>,
+    ) -> RailwayGraph {
+        let mut adjacency: HashMap<i64, Vec<(i64, f64)>> = HashMap::new();
+
+        for segment in segments {
+            let (Some(&from), Some(&to)) = (segment.node_ids.first(), segment.node_ids.last())
+            else {
+                continue;
+            };
+
+            if from == to {
+                continue;
+            }
+
+            let distance = segment.get_distance(node_locations);
+            adjacency.entry(from).or_default().push((to, distance));
+            adjacency.entry(to).or_default().push((from, distance));
+        }
+
+        RailwayGraph { adjacency }
+    }
+
+    fn neighbors(&self, node_id: i64) -> &[(i64, f64)] {
+        self.adjacency
+            .get(&node_id)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Shortest path between two node ids using Dijkstra's algorithm. Returns the
+    /// ordered node ids and the total distance, or `None` if no path exists.
+    pub fn shortest_path_dijkstra(&self, start: i64, goal: i64) -> Option<(Vec<i64>, f64)> {
+        self.search(start, goal, |_| 0.0)
+    }
+
+    /// Shortest path using A* with the great-circle distance to the goal as an
+    /// admissible heuristic - it never overestimates the remaining track
+    /// distance, so the result matches Dijkstra while exploring fewer nodes.
+    pub fn shortest_path_astar(
+        &self,
+        start: i64,
+        goal: i64,
+        node_locations: &HashMap<i64, OsmNode>,
+    ) -> Option<(Vec<i64>, f64)> {
+        let goal_node = node_locations.get(&goal)?;
+        let goal_point = Point::new(goal_node.lon, goal_node.lat);
+
+        self.search(start, goal, |node_id| {
+            match node_locations.get(&node_id) {
+                Some(n) => Haversine::distance(Point::new(n.lon, n.lat), goal_point),
+                // Without a location we fall back to an uninformed estimate.
+                None => 0.0,
+            }
+        })
+    }
+
+    fn search<H>(&self, start: i64, goal: i64, heuristic: H) -> Option<(Vec<i64>, f64)>
+    where
+        H: Fn(i64) -> f64,
+    {
+        let mut dist: HashMap<i64, f64> = HashMap::from([(start, 0.0)]);
+        let mut came_from: HashMap<i64, i64> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+
+        heap.push(Candidate {
+            priority: NonNan::new(heuristic(start)),
+            node_id: start,
+        });
+
+        while let Some(Candidate { node_id, .. }) = heap.pop() {
+            if node_id == goal {
+                // Edge weights accumulate in meters (see `get_distance`); report km.
+                return Some((reconstruct_path(&came_from, goal), dist[&goal] / 1000.0));
+            }
+
+            let current_dist = dist[&node_id];
+            for &(neighbor, weight) in self.neighbors(node_id) {
+                let tentative = current_dist + weight;
+                if tentative < *dist.get(&neighbor).unwrap_or(&f64::INFINITY) {
+                    dist.insert(neighbor, tentative);
+                    came_from.insert(neighbor, node_id);
+                    heap.push(Candidate {
+                        priority: NonNan::new(tentative + heuristic(neighbor)),
+                        node_id: neighbor,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Walk the `came_from` map back from the goal to produce the ordered path.
+fn reconstruct_path(came_from: &HashMap<i64, i64>, goal: i64) -> Vec<i64> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(&previous) = came_from.get(&current) {
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+#[test]
+fn test_shortest_path() {
+    // A small diamond: 1 -> 2 -> 4 is longer than 1 -> 3 -> 4.
+    let node_locations = HashMap::from([
+        (1, OsmNode { lat: 0.0, lon: 0.0, tags: HashMap::new() }),
+        (2, OsmNode { lat: 1.0, lon: 1.0, tags: HashMap::new() }),
+        (3, OsmNode { lat: 0.0, lon: 0.5, tags: HashMap::new() }),
+        (4, OsmNode { lat: 0.0, lon: 1.0, tags: HashMap::new() }),
+    ]);
+
+    let segments = vec![
+        RailwaySegment { name: "a".to_string(), way_id: 1, node_ids: vec![1, 2] },
+        RailwaySegment { name: "b".to_string(), way_id: 2, node_ids: vec![2, 4] },
+        RailwaySegment { name: "c".to_string(), way_id: 3, node_ids: vec![1, 3] },
+        RailwaySegment { name: "d".to_string(), way_id: 4, node_ids: vec![3, 4] },
+    ];
+
+    let graph = RailwayGraph::from_segments(&segments, &node_locations);
+
+    let (path, _) = graph.shortest_path_dijkstra(1, 4).expect("path should exist");
+    assert_eq!(path.first(), Some(&1));
+    assert_eq!(path.last(), Some(&4));
+    assert_eq!(path, vec![1, 3, 4]);
+
+    let (astar_path, _) = graph
+        .shortest_path_astar(1, 4, &node_locations)
+        .expect("path should exist");
+    assert_eq!(astar_path.first(), Some(&1));
+    assert_eq!(astar_path.last(), Some(&4));
+    assert_eq!(astar_path, vec![1, 3, 4]);
+}
@@ -0,0 +1,142 @@
+use crate::{OsmNode, RailwaySegment};
+use geo::{Distance, Haversine, Point};
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
+use std::collections::{HashMap, HashSet};
+
+/// Mean earth radius in meters, used to turn the equirectangular projection
+/// into an approximately metric plane for the `rstar` lookup.
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// A railway node projected into an equirectangular plane. `rstar` works in a
+/// planar metric, so we store projected coordinates for the tree but keep the
+/// original lat/lon around to recompute the true great-circle distance.
+#[derive(Debug, Clone, Copy)]
+struct IndexedNode {
+    node_id: i64,
+    lat: f64,
+    lon: f64,
+    x: f64,
+    y: f64,
+}
+
+impl RTreeObject for IndexedNode {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_point([self.x, self.y])
+    }
+}
+
+impl PointDistance for IndexedNode {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        let dx = self.x - point[0];
+        let dy = self.y - point[1];
+        dx * dx + dy * dy
+    }
+}
+
+/// Project a lat/lon onto an equirectangular plane centered on `reference_latitude`
+/// (radians). Distances in the resulting plane approximate meters.
+fn project(reference_latitude: f64, lat: f64, lon: f64) -> [f64; 2] {
+    let x = EARTH_RADIUS_M * lon.to_radians() * reference_latitude.cos();
+    let y = EARTH_RADIUS_M * lat.to_radians();
+    [x, y]
+}
+
+/// An R-tree index over the railway nodes that lets callers snap arbitrary
+/// coordinates onto the network before routing.
+pub struct RailwayIndex {
+    tree: RTree<IndexedNode>,
+    reference_latitude: f64,
+}
+
+impl RailwayIndex {
+    /// Build the index over the segment-endpoint nodes - the same vertices the
+    /// router connects via [`crate::routing::RailwayGraph`]. Snapping to an
+    /// interior geometry node (or a non-railway node) would hand the router a
+    /// vertex it has no edges for, so those are deliberately excluded.
+    pub fn build(
+        segments: &[RailwaySegment],
+        node_locations: &HashMap<i64, OsmNode>,
+    ) -> RailwayIndex {
+        // The router's vertices are each segment's first and last node.
+        let mut endpoint_ids: HashSet<i64> = HashSet::new();
+        for segment in segments {
+            if let Some(&first) = segment.node_ids.first() {
+                endpoint_ids.insert(first);
+            }
+            if let Some(&last) = segment.node_ids.last() {
+                endpoint_ids.insert(last);
+            }
+        }
+
+        // Use the mean latitude as the standard parallel for the projection so
+        // the planar metric stays faithful across the covered extent.
+        let located: Vec<(i64, &OsmNode)> = endpoint_ids
+            .iter()
+            .filter_map(|&id| node_locations.get(&id).map(|node| (id, node)))
+            .collect();
+
+        let reference_latitude = if located.is_empty() {
+            0.0
+        } else {
+            let sum: f64 = located.iter().map(|(_, n)| n.lat).sum();
+            (sum / located.len() as f64).to_radians()
+        };
+
+        let objects: Vec<IndexedNode> = located
+            .iter()
+            .map(|&(node_id, node)| {
+                let [x, y] = project(reference_latitude, node.lat, node.lon);
+                IndexedNode {
+                    node_id,
+                    lat: node.lat,
+                    lon: node.lon,
+                    x,
+                    y,
+                }
+            })
+            .collect();
+
+        RailwayIndex {
+            tree: RTree::bulk_load(objects),
+            reference_latitude,
+        }
+    }
+
+    /// Snap a coordinate to the nearest railway node, returning its id and the
+    /// true great-circle distance in kilometers.
+    pub fn snap(&self, lat: f64, lon: f64) -> Option<(i64, f64)> {
+        let query = project(self.reference_latitude, lat, lon);
+        let nearest = self.tree.nearest_neighbor(&query)?;
+
+        let dist_m = Haversine::distance(
+            Point::new(lon, lat),
+            Point::new(nearest.lon, nearest.lat),
+        );
+
+        Some((nearest.node_id, dist_m / 1000.0))
+    }
+
+    /// All railway nodes within `radius_km` of the coordinate, each paired with
+    /// its true great-circle distance in kilometers. The planar R-tree query is
+    /// used as a coarse filter and the results are refined with Haversine.
+    pub fn locate_within_distance(&self, lat: f64, lon: f64, radius_km: f64) -> Vec<(i64, f64)> {
+        let query = project(self.reference_latitude, lat, lon);
+        let radius_m = radius_km * 1000.0;
+        let query_point = Point::new(lon, lat);
+
+        let mut results: Vec<(i64, f64)> = self
+            .tree
+            .locate_within_distance(query, radius_m * radius_m)
+            .filter_map(|node| {
+                let dist_km =
+                    Haversine::distance(query_point, Point::new(node.lon, node.lat)) / 1000.0;
+                (dist_km <= radius_km).then_some((node.node_id, dist_km))
+            })
+            .collect();
+
+        results.sort_by(|a, b| a.1.partial_cmp(&b.1).expect("distances are never NaN"));
+        results
+    }
+}